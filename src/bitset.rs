@@ -0,0 +1,119 @@
+use std::cmp::Ordering;
+
+/// A growable set of `u32` indices, stored as a bitmap.
+///
+/// Trailing zero words depend on the history of the set, so comparisons ignore them: two
+/// [BitSet]s holding the same indices are always equal.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// Creates an empty [BitSet].  Does not pre-allocate any memory.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { words: Vec::new() }
+    }
+
+    /// Creates an empty [BitSet], pre-allocated to hold indices below the provided capacity.
+    #[inline]
+    pub fn with_capacity(capacity: u32) -> Self {
+        Self {
+            words: Vec::with_capacity((capacity as usize).div_ceil(64)),
+        }
+    }
+
+    /// Creates a [BitSet] containing every index below the provided length.
+    pub fn full(len: u32) -> Self {
+        let mut words = vec![u64::MAX; (len as usize).div_ceil(64)];
+        if let Some(last) = words.last_mut() {
+            if !len.is_multiple_of(64) {
+                *last = (1 << (len % 64)) - 1;
+            }
+        }
+
+        Self { words }
+    }
+
+    /// Removes every index greater than or equal to the provided length from the set.
+    pub fn truncate(&mut self, len: u32) {
+        self.words.truncate((len as usize).div_ceil(64));
+        if let Some(last) = self.words.last_mut() {
+            if !len.is_multiple_of(64) {
+                *last &= (1 << (len % 64)) - 1;
+            }
+        }
+    }
+
+    /// Returns the number of indices in the set.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns the underlying words of the bitmap.  Bit `i % 64` of word `i / 64` is set iff
+    /// index `i` is in the set.
+    #[inline]
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the underlying words of the bitmap, without its trailing zero words.
+    #[inline]
+    fn trimmed(&self) -> &[u64] {
+        let len = self.words.iter().rposition(|word| *word != 0).map_or(0, |last| last + 1);
+        &self.words[..len]
+    }
+
+    /// Returns `true` if the provided index is in the set.
+    #[inline]
+    pub fn contains(&self, idx: u32) -> bool {
+        match self.words.get(idx as usize / 64) {
+            Some(word) => word & (1 << (idx % 64)) != 0,
+            None => false,
+        }
+    }
+
+    /// Adds the provided index to the set.
+    #[inline]
+    pub fn insert(&mut self, idx: u32) {
+        let word = idx as usize / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        self.words[word] |= 1 << (idx % 64);
+    }
+
+    /// Removes the provided index from the set.
+    #[inline]
+    pub fn remove(&mut self, idx: u32) {
+        if let Some(word) = self.words.get_mut(idx as usize / 64) {
+            *word &= !(1 << (idx % 64));
+        }
+    }
+}
+
+impl PartialEq for BitSet {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl Eq for BitSet {}
+
+impl PartialOrd for BitSet {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BitSet {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.trimmed().cmp(other.trimmed())
+    }
+}
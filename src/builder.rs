@@ -0,0 +1,96 @@
+use std::{marker::PhantomData, panic::RefUnwindSafe, sync::Arc};
+
+use crate::{
+    hook::{AutoCompact, Hook},
+    ArenaError, RefRemap, ReusePolicy, SlotArena,
+};
+
+/// Configures and creates a [SlotArena], see [SlotArena::builder].
+pub struct SlotArenaBuilder<T> {
+    capacity: u32,
+    free_capacity: u32,
+    policy: ReusePolicy,
+    bound: u32,
+    auto_compact: Option<Result<AutoCompact, ArenaError>>,
+    marker: PhantomData<T>,
+}
+
+impl<T> SlotArenaBuilder<T> {
+    /// Creates a [SlotArenaBuilder] with the default configuration of [SlotArena::new].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            capacity: 0,
+            free_capacity: 0,
+            policy: ReusePolicy::Lifo,
+            bound: u32::MAX,
+            auto_compact: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Pre-allocates the memory block for the provided number of items.
+    #[inline]
+    pub fn capacity(mut self, capacity: u32) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Pre-allocates the list of freed slots for the provided number of slots.
+    #[inline]
+    pub fn free_capacity(mut self, free_capacity: u32) -> Self {
+        self.free_capacity = free_capacity;
+        self
+    }
+
+    /// Sets the [ReusePolicy] of the [SlotArena].
+    #[inline]
+    pub fn reuse_policy(mut self, policy: ReusePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Limits the memory block to the provided number of slots, see [SlotArena::bound].
+    #[inline]
+    pub fn bounded(mut self, bound: u32) -> Self {
+        self.bound = bound;
+        self
+    }
+
+    /// Enables auto-compaction, see [SlotArena::set_auto_compact].
+    pub fn auto_compact<F: Fn(&RefRemap<T>) + Send + Sync + RefUnwindSafe + 'static>(
+        mut self,
+        threshold: f64,
+        fixup: F,
+    ) -> Self {
+        self.auto_compact = Some(AutoCompact::new(threshold, fixup));
+        self
+    }
+
+    /// Creates the configured [SlotArena].
+    ///
+    /// # Errors
+    /// Returns an error if either capacity exceeds the bound, or if the auto-compaction threshold
+    /// is negative or NaN.
+    pub fn build(self) -> Result<SlotArena<T>, ArenaError> {
+        if self.capacity > self.bound || self.free_capacity > self.bound {
+            return Err(ArenaError::CapacityExceeded(self.bound));
+        }
+
+        let mut arena = SlotArena::with_capacities(self.capacity, self.free_capacity);
+        arena.policy = self.policy;
+        arena.bound = self.bound;
+        if let Some(auto_compact) = self.auto_compact {
+            arena.auto_compact = Hook::new(Arc::new(auto_compact?));
+        }
+
+        Ok(arena)
+    }
+}
+
+impl<T> Default for SlotArenaBuilder<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
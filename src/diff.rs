@@ -0,0 +1,49 @@
+use crate::{Ref, SlotArena};
+
+/// The changes between two snapshots of a [SlotArena], created by [SlotArena::diff].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArenaDiff<T> {
+    /// The slots alive only in the newer snapshot, with their values.
+    pub added: Vec<(Ref<T>, T)>,
+    /// The slots alive only in the older snapshot.
+    pub removed: Vec<Ref<T>>,
+    /// The slots alive in both snapshots whose value changed, with their old and new values.
+    pub changed: Vec<(Ref<T>, T, T)>,
+}
+
+impl<T> ArenaDiff<T> {
+    /// Returns `true` if both snapshots hold the same alive items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<T: PartialEq + Clone> SlotArena<T> {
+    /// Computes the changes from this [SlotArena] to a newer snapshot of it, comparing the alive
+    /// slots at matching indices.  Every list of the returned [ArenaDiff] is in index order.
+    ///
+    /// A slot freed and then reused between the snapshots is reported as changed, not as removed
+    /// and added.
+    pub fn diff(&self, newer: &SlotArena<T>) -> ArenaDiff<T> {
+        let mut diff = ArenaDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+
+        for idx in 0..self.slot_count().max(newer.slot_count()) {
+            let value = Ref::from_raw(idx);
+            match (self.try_get(value), newer.try_get(value)) {
+                (Some(old), Some(new)) if old != new => {
+                    diff.changed.push((value, old.clone(), new.clone()));
+                }
+                (Some(_), None) => diff.removed.push(value),
+                (None, Some(new)) => diff.added.push((value, new.clone())),
+                _ => {}
+            }
+        }
+
+        diff
+    }
+}
@@ -0,0 +1,52 @@
+use std::{error::Error, fmt::Display};
+
+/// An error returned when assembling or growing a [SlotArena](crate::SlotArena) fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArenaError {
+    /// A freed slot index is outside the bounds of the memory block.
+    FreeOutOfBounds(u32),
+    /// A slot index is freed more than once.
+    DuplicateFree(u32),
+    /// The number of slots would exceed the provided limit.
+    CapacityExceeded(u32),
+    /// An auto-compaction threshold is negative or NaN.
+    InvalidThreshold,
+    /// A slot reserved by [SlotArena::reserve_slot](crate::SlotArena::reserve_slot) is not filled.
+    UnfilledReservation(u32),
+}
+
+impl Display for ArenaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FreeOutOfBounds(idx) => write!(f, "freed slot #{idx} is out of bounds"),
+            Self::DuplicateFree(idx) => write!(f, "slot #{idx} is freed more than once"),
+            Self::CapacityExceeded(limit) => {
+                write!(f, "capacity exceeded: a SlotArena holds at most {limit} slots")
+            }
+            Self::InvalidThreshold => write!(f, "auto-compaction threshold is negative or NaN"),
+            Self::UnfilledReservation(idx) => write!(f, "reserved slot #{idx} is not filled"),
+        }
+    }
+}
+
+impl Error for ArenaError {}
+
+/// An error returned when getting mutable references to several values at once fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DisjointError {
+    /// The reference with the provided index is invalid.
+    Invalid(u32),
+    /// The reference with the provided index was requested more than once.
+    Aliased(u32),
+}
+
+impl Display for DisjointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(idx) => write!(f, "invalid Ref #{idx}"),
+            Self::Aliased(idx) => write!(f, "Ref #{idx} is requested more than once"),
+        }
+    }
+}
+
+impl Error for DisjointError {}
@@ -0,0 +1,78 @@
+use std::fmt::Debug;
+
+use crate::{Ref, SlotArena};
+
+/// A read-only [SlotArena], created by [SlotArena::freeze].
+///
+/// A [FrozenArena] keeps a dense index of its alive items, so iterating through it never visits a
+/// freed slot.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FrozenArena<T> {
+    arena: SlotArena<T>,
+    alive: Vec<Ref<T>>,
+}
+
+impl<T> FrozenArena<T> {
+    /// Returns the mutable [SlotArena] this [FrozenArena] was created from.
+    #[inline]
+    pub fn thaw(self) -> SlotArena<T> {
+        self.arena
+    }
+
+    /// Returns the number of alive items in the [FrozenArena].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// Returns `true` if the [FrozenArena] has no alive items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.alive.is_empty()
+    }
+
+    /// Returns `true` if the provided reference is valid, see [SlotArena::is_valid].
+    #[inline]
+    pub fn is_valid(&self, value: Ref<T>) -> bool {
+        self.arena.is_valid(value)
+    }
+
+    /// Returns a non-opaque reference to the provided value.
+    ///
+    /// # Panics
+    /// See [SlotArena::get].
+    #[inline]
+    #[track_caller]
+    pub fn get(&self, value: Ref<T>) -> &T {
+        self.arena.get(value)
+    }
+
+    /// Attempts to get the value of the provided reference, returns [`None`] if the reference was
+    /// invalid.
+    #[inline]
+    pub fn try_get(&self, value: Ref<T>) -> Option<&T> {
+        self.arena.try_get(value)
+    }
+
+    /// Returns an iterator through the alive items in the [FrozenArena], in index order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Ref<T>, &T)> {
+        self.alive
+            .iter()
+            .map(|idx| (*idx, &self.arena.raw[idx.to_raw() as usize]))
+    }
+}
+
+impl<T> SlotArena<T> {
+    /// Consumes the [SlotArena], returning a read-only [FrozenArena].  Use [FrozenArena::thaw] to
+    /// get the [SlotArena] back.
+    pub fn freeze(self) -> FrozenArena<T> {
+        let alive = self.iter().map(|(idx, _)| idx).collect();
+        FrozenArena { arena: self, alive }
+    }
+}
+
+impl<T: Debug> Debug for FrozenArena<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
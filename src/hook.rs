@@ -0,0 +1,105 @@
+use std::{cmp::Ordering, fmt::Debug, panic::RefUnwindSafe, sync::Arc};
+
+use crate::{ArenaError, RefRemap};
+
+/// An optional user callback stored in a [SlotArena](crate::SlotArena).  Hooks are not part of
+/// the arena's logical value, so they are ignored by comparisons.
+pub(crate) struct Hook<F: ?Sized>(Option<Arc<F>>);
+
+impl<F: ?Sized> Hook<F> {
+    /// Creates an unset [Hook].
+    #[inline]
+    pub const fn none() -> Self {
+        Self(None)
+    }
+
+    /// Creates a [Hook] set to the provided callback.
+    #[inline]
+    pub fn new(f: Arc<F>) -> Self {
+        Self(Some(f))
+    }
+
+    /// Returns the callback, if set.
+    #[inline]
+    pub fn get(&self) -> Option<&F> {
+        self.0.as_deref()
+    }
+}
+
+impl<F: ?Sized> Clone for Hook<F> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<F: ?Sized> Default for Hook<F> {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<F: ?Sized> Debug for Hook<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(_) => write!(f, "Hook(Some(..))"),
+            None => write!(f, "Hook(None)"),
+        }
+    }
+}
+
+impl<F: ?Sized> PartialEq for Hook<F> {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<F: ?Sized> Eq for Hook<F> {}
+
+impl<F: ?Sized> PartialOrd for Hook<F> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: ?Sized> Ord for Hook<F> {
+    #[inline]
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+/// A callback invoked with the [RefRemap] of each compaction.  The [RefRemap] is type-erased so
+/// that storing the callback does not make the arena invariant in its item type.
+pub(crate) type Fixup = dyn Fn(RefRemap<()>) + Send + Sync + RefUnwindSafe;
+
+/// The auto-compaction policy of a [SlotArena](crate::SlotArena).
+pub(crate) struct AutoCompact {
+    /// The fragmentation above which the arena compacts itself.
+    pub threshold: f64,
+    /// The callback invoked after compaction.
+    pub fixup: Box<Fixup>,
+}
+
+impl AutoCompact {
+    /// Creates an auto-compaction policy invoking `fixup` with the [RefRemap] of the arena's items.
+    ///
+    /// # Errors
+    /// Returns an error if the threshold is negative or NaN.
+    pub fn new<T, F>(threshold: f64, fixup: F) -> Result<Self, ArenaError>
+    where
+        F: Fn(&RefRemap<T>) + Send + Sync + RefUnwindSafe + 'static,
+    {
+        if threshold.is_nan() || threshold < 0.0 {
+            return Err(ArenaError::InvalidThreshold);
+        }
+
+        Ok(Self {
+            threshold,
+            fixup: Box::new(move |remap: RefRemap<()>| fixup(&remap.cast())),
+        })
+    }
+}
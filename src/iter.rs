@@ -0,0 +1,49 @@
+use std::{iter::FusedIterator, vec};
+
+use crate::{bitset::BitSet, Ref, SlotArena};
+
+/// An iterator moving the alive items out of a [SlotArena], created by its
+/// [IntoIterator] implementation.
+pub struct IntoIter<T> {
+    items: vec::IntoIter<T>,
+    alive: BitSet,
+    idx: u32,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (Ref<T>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.items.next()?;
+            let idx = self.idx;
+            self.idx += 1;
+            if self.alive.contains(idx) {
+                return Some((Ref::from_raw(idx), item));
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.items.len()))
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SlotArena<T> {
+    type Item = (Ref<T>, T);
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the [SlotArena], returning an iterator through its alive items in index order.
+    /// Use [SlotArena::drain_into_iter] to keep the memory block for reuse instead.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            items: self.raw.into_iter(),
+            alive: self.alive,
+            idx: 0,
+        }
+    }
+}
@@ -1,16 +1,66 @@
 #![doc = include_str!("../README.md")]
 
+mod bitset;
+mod builder;
+mod diff;
+mod error;
+mod frozen;
+mod hook;
+mod iter;
+mod macros;
+#[cfg(feature = "rayon")]
+mod par;
+mod policy;
 mod r#ref;
+mod remap;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(test)]
+mod tests;
 
-use std::fmt::Debug;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    hash::Hash,
+    ops::{Deref, DerefMut},
+    panic::RefUnwindSafe,
+    sync::Arc,
+};
 
+use bitset::BitSet;
+use hook::{AutoCompact, Hook};
+
+pub use builder::*;
+pub use diff::*;
+pub use error::*;
+pub use frozen::*;
+pub use iter::*;
+pub use policy::*;
 pub use r#ref::*;
+pub use remap::*;
 
 /// A block of memory accessed using 32-bit [Ref]s rather than 64-bit memory addresses.
-#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// If `T` is zero-sized, the memory block itself never allocates, and the [SlotArena] only uses a
+/// bit per slot plus 4 bytes per freed slot: a `SlotArena<()>` is an efficient ID allocator (see
+/// [SlotArena::alloc_id]).
+///
+/// Items are stored contiguously, so growing the memory block may move them: [Ref]s stay valid,
+/// but raw pointers into the [SlotArena] do not.  Items that must keep a stable address should be
+/// boxed, as in `SlotArena<Box<T>>`.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
 pub struct SlotArena<T> {
     raw: Vec<T>,
-    free: Vec<Ref<T>>,
+    free: VecDeque<Ref<T>>,
+    alive: BitSet,
+    reserved: BitSet,
+    reserved_len: u32,
+    policy: ReusePolicy,
+    bound: u32,
+    version: u32,
+    grow_hook: Hook<dyn Fn(usize, usize) + Send + Sync + RefUnwindSafe>,
+    auto_compact: Hook<AutoCompact>,
 }
 
 impl<T> SlotArena<T> {
@@ -19,78 +69,537 @@ impl<T> SlotArena<T> {
     pub const fn new() -> Self {
         Self {
             raw: Vec::new(),
-            free: Vec::new(),
+            free: VecDeque::new(),
+            alive: BitSet::new(),
+            reserved: BitSet::new(),
+            reserved_len: 0,
+            policy: ReusePolicy::Lifo,
+            bound: u32::MAX,
+            version: 0,
+            grow_hook: Hook::none(),
+            auto_compact: Hook::none(),
         }
     }
 
     /// Creates an empty [SlotArena], pre-allocated for the provided capacity.
     #[inline]
     pub fn with_capacity(capacity: u32) -> Self {
+        Self::with_capacities(capacity, 0)
+    }
+
+    /// Creates an empty [SlotArena], pre-allocated for the provided capacity of items and of freed
+    /// slots.
+    #[inline]
+    pub fn with_capacities(value_cap: u32, free_cap: u32) -> Self {
+        Self {
+            raw: Vec::with_capacity(value_cap as usize),
+            free: VecDeque::with_capacity(free_cap as usize),
+            alive: BitSet::with_capacity(value_cap),
+            ..Self::new()
+        }
+    }
+
+    /// Creates a [SlotArena] with `count` freed slots holding values created by `init`, so the
+    /// first `count` insertions reuse them without growing the memory block.  They are reused in
+    /// index order under [ReusePolicy::Lifo], and each prefilled value is dropped when its slot is
+    /// reused.
+    pub fn with_prefilled(count: u32, mut init: impl FnMut() -> T) -> Self {
         Self {
-            raw: Vec::with_capacity(capacity as usize),
-            free: Vec::new(),
+            raw: (0..count).map(|_| init()).collect(),
+            free: (0..count).rev().map(Ref::from_raw).collect(),
+            alive: BitSet::with_capacity(count),
+            ..Self::new()
         }
     }
 
+    /// Returns a [SlotArenaBuilder] to configure a new [SlotArena].
+    #[inline]
+    pub fn builder() -> SlotArenaBuilder<T> {
+        SlotArenaBuilder::new()
+    }
+
+    /// Assembles a [SlotArena] from its memory block and list of freed slots, in the order they
+    /// will be reused under [ReusePolicy::Lifo].
+    ///
+    /// # Errors
+    /// Returns an error if the memory block holds more than `u32::MAX` slots, if a freed slot is
+    /// out of its bounds, or if a slot is freed more than once.
+    pub fn from_parts(raw: Vec<T>, free: Vec<Ref<T>>) -> Result<Self, ArenaError> {
+        let len = match raw.len() {
+            0 => 0,
+            len => index_from_usize(len - 1)? + 1,
+        };
+
+        let mut alive = BitSet::full(len);
+        for idx in &free {
+            if idx.to_raw() as usize >= raw.len() {
+                return Err(ArenaError::FreeOutOfBounds(idx.to_raw()));
+            }
+
+            if !alive.contains(idx.to_raw()) {
+                return Err(ArenaError::DuplicateFree(idx.to_raw()));
+            }
+
+            alive.remove(idx.to_raw());
+        }
+
+        Ok(Self {
+            raw,
+            free: free.into(),
+            alive,
+            ..Self::new()
+        })
+    }
+
+    /// Consumes the [SlotArena], returning its memory block and list of freed slots, in the form
+    /// accepted by [SlotArena::from_parts].
+    ///
+    /// Freed slots of the memory block still hold their stale values.
+    ///
+    /// # Panics
+    /// Panics if a slot reserved by [SlotArena::reserve_slot] is not filled, as the returned parts
+    /// cannot represent it.
+    #[inline]
+    #[track_caller]
+    pub fn into_parts(self) -> (Vec<T>, Vec<Ref<T>>) {
+        if let Err(err) = self.check_filled() {
+            panic!("{err}");
+        }
+
+        (self.raw, self.free.into())
+    }
+
+    /// Returns the [ReusePolicy] used to pick which freed slot is reused on insertion.
+    #[inline]
+    pub fn reuse_policy(&self) -> ReusePolicy {
+        self.policy
+    }
+
+    /// Sets the [ReusePolicy] used to pick which freed slot is reused on insertion.  Affects the
+    /// slots that are already free as well.
+    #[inline]
+    pub fn set_reuse_policy(&mut self, policy: ReusePolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns the maximum number of slots of the memory block, `u32::MAX` unless set by
+    /// [SlotArenaBuilder::bounded].  Inserting past it fails, except into freed slots.
+    #[inline]
+    pub fn bound(&self) -> u32 {
+        self.bound
+    }
+
+    /// Returns the user-defined schema version of the [SlotArena], `0` by default.  The version is
+    /// kept when the [SlotArena] is serialized, so loaders can detect and migrate old data.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Sets the user-defined schema version of the [SlotArena].
+    #[inline]
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// Makes the [SlotArena] compact itself with [SlotArena::defragment] whenever freeing an item
+    /// brings its [fragmentation](SlotArena::fragmentation) above the provided threshold.
+    ///
+    /// Compaction moves items, invalidating outstanding [Ref]s to them: the `fixup` callback is
+    /// invoked with the [RefRemap] of every compaction, and must be used to update them.  Clones of
+    /// the [SlotArena] do not inherit the auto-compaction, as the fixup only updates the [Ref]s
+    /// into this one.
+    ///
+    /// # Errors
+    /// Returns an error if the threshold is negative or NaN, leaving the previous auto-compaction
+    /// in place.
+    pub fn set_auto_compact<F: Fn(&RefRemap<T>) + Send + Sync + RefUnwindSafe + 'static>(
+        &mut self,
+        threshold: f64,
+        fixup: F,
+    ) -> Result<(), ArenaError> {
+        self.auto_compact = Hook::new(Arc::new(AutoCompact::new(threshold, fixup)?));
+        Ok(())
+    }
+
+    /// Disables the auto-compaction set by [SlotArena::set_auto_compact].
+    #[inline]
+    pub fn remove_auto_compact(&mut self) {
+        self.auto_compact = Hook::none();
+    }
+
+    /// Sets a callback invoked with the old and new capacity of the memory block whenever it
+    /// grows, replacing the previous one.
+    pub fn set_grow_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(usize, usize) + Send + Sync + RefUnwindSafe + 'static,
+    {
+        self.grow_hook = Hook::new(Arc::new(hook));
+    }
+
+    /// Removes the callback set by [SlotArena::set_grow_hook].
+    #[inline]
+    pub fn remove_grow_hook(&mut self) {
+        self.grow_hook = Hook::none();
+    }
+
+    /// Reserves capacity for at least `additional` more items in the memory block.
+    pub fn reserve(&mut self, additional: u32) {
+        let capacity = self.raw.capacity();
+        self.raw.reserve(additional as usize);
+        self.notify_grow(capacity);
+    }
+
     /// Frees the provided value.  A value should not be used once it is freed, as it may be
-    /// replaced by another value.
+    /// replaced by another value.  Freeing a reserved slot cancels the reservation, and freeing a
+    /// reference that is neither alive nor reserved has no effect.
     #[inline]
     pub fn free(&mut self, value: Ref<T>) {
-        self.free.push(value);
+        let idx = value.to_raw();
+        if self.alive.contains(idx) {
+            self.alive.remove(idx);
+        } else if self.reserved.contains(idx) {
+            self.reserved.remove(idx);
+            self.reserved_len -= 1;
+        } else {
+            return;
+        }
+
+        self.free.push_back(value);
+        self.maybe_auto_compact();
+    }
+
+    /// Frees every alive item for which the predicate returns `true`, returning the number of
+    /// items freed.
+    pub fn remove_all<F: FnMut(Ref<T>, &T) -> bool>(&mut self, mut pred: F) -> usize {
+        let mut removed = 0;
+        for idx in 0..self.slot_count() {
+            let value = Ref::from_raw(idx);
+            if self.alive.contains(idx) && pred(value, &self.raw[idx as usize]) {
+                self.alive.remove(idx);
+                self.free.push_back(value);
+                removed += 1;
+            }
+        }
+
+        self.maybe_auto_compact();
+        removed
+    }
+
+    /// Shortens the memory block to the provided number of slots, dropping every item past it.
+    /// Has no effect if the memory block is already shorter.
+    ///
+    /// Returns the [Ref]s to the alive items and reserved slots that were dropped, which are now
+    /// invalid.
+    pub fn truncate(&mut self, len: u32) -> Vec<Ref<T>> {
+        let invalidated: Vec<_> = (len..self.slot_count())
+            .filter(|idx| self.alive.contains(*idx) || self.reserved.contains(*idx))
+            .map(Ref::from_raw)
+            .collect();
+
+        self.free.retain(|idx| idx.to_raw() < len);
+        self.reserved_len -= (len..self.slot_count())
+            .filter(|idx| self.reserved.contains(*idx))
+            .count() as u32;
+
+        self.raw.truncate(len as usize);
+        self.alive.truncate(len);
+        self.reserved.truncate(len);
+        invalidated
     }
 
-    /// Inserts a value into the [SlotArena], returning a [Ref] to it.
+    /// Inserts a value into the [SlotArena], returning a [Ref] to it.  Freed slots are reused
+    /// first, in the order given by the [ReusePolicy].
     ///
     /// # Panics
-    /// Panics if the number of items in this [SlotArena] exceeds `u32::MAX`.
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[track_caller]
     pub fn insert(&mut self, value: T) -> Ref<T> {
-        match self.free.pop() {
+        match self.pop_free() {
             Some(idx) => {
                 self.raw[idx.to_raw() as usize] = value;
+                self.alive.insert(idx.to_raw());
                 idx
             }
-            None => {
-                let idx = Ref::from_raw(self.raw.len() as u32);
-                self.raw.push(value);
-                idx
-            }
+            None => match self.push(value) {
+                Ok(idx) => idx,
+                Err(err) => panic!("{err}"),
+            },
         }
     }
 
+    /// Returns the [Ref]s the next `count` insertions would receive, without inserting anything:
+    /// freed slots in the order given by the [ReusePolicy], then new slots at the end of the
+    /// memory block.  Fewer [Ref]s are returned if the [SlotArena] would be full.
+    pub fn preview_inserts(&self, count: usize) -> Vec<Ref<T>> {
+        let mut refs: Vec<_> = match self.policy {
+            ReusePolicy::Lifo => self.free.iter().rev().take(count).copied().collect(),
+            ReusePolicy::Fifo => self.free.iter().take(count).copied().collect(),
+        };
+
+        let fresh = count - refs.len();
+        refs.extend((self.slot_count()..self.bound).take(fresh).map(Ref::from_raw));
+        refs
+    }
+
+    /// Inserts a value into the [SlotArena], returning a [Ref] to it along with `true` if a freed
+    /// slot was reused, or `false` if the memory block grew.
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[track_caller]
+    pub fn insert_tracked(&mut self, value: T) -> (Ref<T>, bool) {
+        let reused = !self.free.is_empty();
+        (self.insert(value), reused)
+    }
+
     /// Attempts to insert a value into the [SlotArena], returning [`None`] if it is full.
     pub fn try_insert(&mut self, value: T) -> Option<Ref<T>> {
-        match self.free.pop() {
+        match self.pop_free() {
             Some(idx) => {
                 self.raw[idx.to_raw() as usize] = value;
+                self.alive.insert(idx.to_raw());
                 Some(idx)
             }
-            None => {
-                if self.raw.len() == u32::MAX as usize {
-                    return None;
-                }
+            None => self.push(value).ok(),
+        }
+    }
 
-                let idx = Ref::from_raw(self.raw.len() as u32);
-                self.raw.push(value);
-                Some(idx)
+    /// Inserts every value of the iterator at the end of the [SlotArena], returning the range of
+    /// [Ref]s to them.  Freed slots are not reused, so the [Ref]s are always contiguous.
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[track_caller]
+    pub fn insert_contiguous<I: IntoIterator<Item = T>>(&mut self, iter: I) -> RefRange<T> {
+        let start = self.slot_count();
+        for value in iter {
+            if let Err(err) = self.push(value) {
+                panic!("{err}");
+            }
+        }
+
+        RefRange::new(start, self.slot_count())
+    }
+
+    /// Reserves a slot, returning its [Ref] to be filled later with [SlotArena::fill].  The [Ref]
+    /// is invalid until then.
+    ///
+    /// Since only safe Rust is used, the slot holds the provided placeholder until it is filled.  A
+    /// reserved slot is neither alive nor free: [SlotArena::defragment] and [SlotArena::sort_by]
+    /// move and remap it like an alive item, [SlotArena::truncate] returns it, and
+    /// [SlotArena::free] cancels the reservation.  [SlotArena::into_parts] and serialization
+    /// refuse unfilled reservations, which their formats cannot represent.
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[track_caller]
+    pub fn reserve_slot_with(&mut self, placeholder: T) -> Ref<T> {
+        let idx = self.insert(placeholder);
+        self.alive.remove(idx.to_raw());
+        self.reserved.insert(idx.to_raw());
+        self.reserved_len += 1;
+        idx
+    }
+
+    /// Fills a slot reserved by [SlotArena::reserve_slot_with] with the provided value, making its
+    /// [Ref] valid.
+    ///
+    /// # Panics
+    /// Panics if the provided reference is not a reserved slot.
+    #[track_caller]
+    pub fn fill(&mut self, value: Ref<T>, item: T) {
+        let idx = value.to_raw();
+        assert!(self.reserved.contains(idx), "invalid Ref #{idx}: slot is not reserved");
+
+        self.raw[idx as usize] = item;
+        self.reserved.remove(idx);
+        self.alive.insert(idx);
+        self.reserved_len -= 1;
+    }
+
+    /// Inserts a value into the [SlotArena], returning a [Ref] to it along with a mutable
+    /// reference to the inserted value.
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[track_caller]
+    pub fn insert_mut(&mut self, value: T) -> (Ref<T>, &mut T) {
+        let idx = self.insert(value);
+        (idx, &mut self.raw[idx.to_raw() as usize])
+    }
+
+    /// Attempts to insert a value into the [SlotArena], returning a [Ref] to it along with a
+    /// mutable reference to the inserted value, or [`None`] if it is full.
+    pub fn try_insert_mut(&mut self, value: T) -> Option<(Ref<T>, &mut T)> {
+        let idx = self.try_insert(value)?;
+        Some((idx, &mut self.raw[idx.to_raw() as usize]))
+    }
+
+    /// Returns the number of alive items in the [SlotArena].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.raw.len() - self.free.len() - self.reserved_len as usize
+    }
+
+    /// Returns the number of freed slots in the [SlotArena], waiting to be reused.
+    #[inline]
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns `true` if the [SlotArena] has no alive items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the ratio of freed slots to total slots in the memory block, from `0.0` (no freed
+    /// slot) to `1.0` (only freed slots).
+    #[inline]
+    pub fn fragmentation(&self) -> f64 {
+        if self.raw.is_empty() {
+            0.0
+        } else {
+            self.free.len() as f64 / self.raw.len() as f64
+        }
+    }
+
+    /// Moves the alive items of the [SlotArena] to the start of the memory block, keeping their
+    /// order, and drops every freed slot.  Reserved slots are moved like alive items.
+    ///
+    /// The returned [RefRemap] holds the new [Ref] of every alive item and reserved slot, moved or
+    /// not, which must be used to update outstanding [Ref]s.
+    pub fn defragment(&mut self) -> RefRemap<T> {
+        let mut free = BitSet::with_capacity(self.slot_count());
+        for idx in &self.free {
+            free.insert(idx.to_raw());
+        }
+
+        let mut remap = RefRemap::new();
+        let mut alive = BitSet::with_capacity(self.slot_count());
+        let mut reserved = BitSet::new();
+        let mut len = 0;
+        for idx in 0..self.slot_count() {
+            if free.contains(idx) {
+                continue;
+            }
+
+            if self.alive.contains(idx) {
+                alive.insert(len);
+            } else {
+                reserved.insert(len);
             }
+
+            self.raw.swap(idx as usize, len as usize);
+            remap.insert(Ref::from_raw(idx), Ref::from_raw(len));
+            len += 1;
         }
+
+        self.raw.truncate(len as usize);
+        self.free.clear();
+        self.alive = alive;
+        self.reserved = reserved;
+        remap
+    }
+
+    /// Checks the internal invariants of the [SlotArena], for use in tests and fuzzing.
+    ///
+    /// # Panics
+    /// Panics with a description of the first violated invariant:
+    /// - every freed slot is in bounds, not alive, and freed only once;
+    /// - every alive slot is in bounds;
+    /// - every reserved slot is in bounds, neither alive nor freed, and counted;
+    /// - the alive, freed and reserved slots add up to the number of slots;
+    /// - the number of slots does not exceed [SlotArena::bound].
+    #[track_caller]
+    pub fn assert_invariants(&self) {
+        let mut free = BitSet::with_capacity(self.slot_count());
+        for idx in &self.free {
+            let idx = idx.to_raw();
+            assert!(
+                (idx as usize) < self.raw.len(),
+                "freed slot #{idx} is out of bounds ({} slots)",
+                self.raw.len()
+            );
+            assert!(!self.alive.contains(idx), "freed slot #{idx} is alive");
+            assert!(!free.contains(idx), "slot #{idx} is freed more than once");
+            free.insert(idx);
+        }
+
+        let alive = self.alive.count();
+        assert!(
+            (0..self.slot_count()).filter(|idx| self.alive.contains(*idx)).count() == alive,
+            "an alive slot is out of bounds ({} slots)",
+            self.raw.len()
+        );
+        let reserved = self.reserved.count();
+        assert!(
+            (0..self.slot_count()).filter(|idx| self.reserved.contains(*idx)).count() == reserved,
+            "a reserved slot is out of bounds ({} slots)",
+            self.raw.len()
+        );
+        for idx in (0..self.slot_count()).filter(|idx| self.reserved.contains(*idx)) {
+            assert!(!self.alive.contains(idx), "reserved slot #{idx} is alive");
+            assert!(!free.contains(idx), "reserved slot #{idx} is freed");
+        }
+        assert!(
+            reserved == self.reserved_len as usize,
+            "{reserved} reserved slots are counted as {}",
+            self.reserved_len
+        );
+        assert!(
+            alive + self.free.len() + reserved == self.raw.len(),
+            "{alive} alive slots, {} freed and {reserved} reserved do not add up to {} slots",
+            self.free.len(),
+            self.raw.len()
+        );
+        assert!(
+            self.raw.len() <= self.bound as usize,
+            "{} slots exceed the bound of {}",
+            self.raw.len(),
+            self.bound
+        );
     }
 
     /// Returns `true` if the provided reference is valid (if the reference is in the bounds of the
-    /// memory block AND the reference is not free).
+    /// memory block AND its slot is alive, neither freed nor reserved).
     #[inline]
     pub fn is_valid(&self, value: Ref<T>) -> bool {
-        !self.free.contains(&value) && (value.to_raw() as usize) < self.raw.len()
+        self.alive.contains(value.to_raw())
+    }
+
+    /// Returns the [Ref] to the alive item at the provided slot index, such as one returned by
+    /// [Ref::raw_index], or [`None`] if the slot is not alive.
+    #[inline]
+    pub fn current_ref(&self, index: u32) -> Option<Ref<T>> {
+        self.alive.contains(index).then_some(Ref::from_raw(index))
+    }
+
+    /// Returns a bitmap of the slots of the [SlotArena], where a bit is set iff its slot is alive.
+    ///
+    /// Bits are ordered from least to most significant: slot `i` is represented by bit `i % 64` of
+    /// word `i / 64`.  The bitmap is exactly long enough to hold every slot; bits past the end are
+    /// always unset.
+    pub fn occupancy(&self) -> Vec<u64> {
+        let mut words = self.alive.words().to_vec();
+        words.resize(self.raw.len().div_ceil(64), 0);
+        words
     }
 
     /// Returns a non-opaque reference to the provided value.
     ///
     /// # Panics
-    /// Panics if the provided reference is invalid.
+    /// Panics if the provided reference is out of bounds, or if it has been freed or reserved
+    /// (debug builds only).  The panic message includes the index of the offending reference.
     #[inline]
+    #[track_caller]
     pub fn get(&self, value: Ref<T>) -> &T {
-        debug_assert!(self.is_valid(value));
-        &self.raw[value.to_raw() as usize]
+        self.debug_assert_not_freed(value);
+        match self.raw.get(value.to_raw() as usize) {
+            Some(item) => item,
+            None => panic!("invalid Ref #{}: out of bounds", value.to_raw()),
+        }
     }
 
     /// Attempts to get the value of the provided reference, returns [`None`] if the reference was
@@ -106,11 +615,16 @@ impl<T> SlotArena<T> {
     /// Returns a non-opaque reference to the provided value.
     ///
     /// # Panics
-    /// Panics if the provided reference is invalid.
+    /// Panics if the provided reference is out of bounds, or if it has been freed or reserved
+    /// (debug builds only).  The panic message includes the index of the offending reference.
     #[inline]
+    #[track_caller]
     pub fn get_mut(&mut self, value: Ref<T>) -> &mut T {
-        debug_assert!(self.is_valid(value));
-        &mut self.raw[value.to_raw() as usize]
+        self.debug_assert_not_freed(value);
+        match self.raw.get_mut(value.to_raw() as usize) {
+            Some(item) => item,
+            None => panic!("invalid Ref #{}: out of bounds", value.to_raw()),
+        }
     }
 
     /// Attempts to get the value of the provided reference, returns [`None`] if the reference was
@@ -123,22 +637,599 @@ impl<T> SlotArena<T> {
         }
     }
 
+    /// Attempts to get the value of the provided reference along with the reference itself,
+    /// returns [`None`] if the reference was invalid.  Meant for iterator chains such as
+    /// `refs.iter().filter_map(|idx| arena.resolve(*idx))`.
+    #[inline]
+    pub fn resolve(&self, value: Ref<T>) -> Option<(Ref<T>, &T)> {
+        self.try_get(value).map(|item| (value, item))
+    }
+
+    /// Attempts to get a mutable reference to the value of the provided reference along with the
+    /// reference itself, returns [`None`] if the reference was invalid.
+    #[inline]
+    pub fn resolve_mut(&mut self, value: Ref<T>) -> Option<(Ref<T>, &mut T)> {
+        self.try_get_mut(value).map(|item| (value, item))
+    }
+
+    /// Attempts to get mutable references to the values of all the provided references at once,
+    /// in the same order.  Returns [`None`] if any reference is invalid, or if two of them are
+    /// equal.
+    pub fn get_disjoint_mut_slice<'a>(&'a mut self, refs: &[Ref<T>]) -> Option<Vec<&'a mut T>> {
+        if !refs.iter().all(|value| self.is_valid(*value)) {
+            return None;
+        }
+
+        let mut order: Vec<usize> = (0..refs.len()).collect();
+        order.sort_unstable_by_key(|&pos| refs[pos]);
+        if order.windows(2).any(|pair| refs[pair[0]] == refs[pair[1]]) {
+            return None;
+        }
+
+        let sorted = self.get_sorted_disjoint_mut(order.iter().map(|&pos| refs[pos]));
+        let mut values: Vec<Option<&'a mut T>> = (0..refs.len()).map(|_| None).collect();
+        for (pos, value) in order.into_iter().zip(sorted) {
+            values[pos] = Some(value);
+        }
+
+        values.into_iter().collect()
+    }
+
+    /// Attempts to get mutable references to the values of all the distinct provided references
+    /// at once, in the order they first appear.  Returns [`None`] if any reference is invalid.
+    pub fn get_deduped_mut<'a>(&'a mut self, refs: &[Ref<T>]) -> Option<Vec<&'a mut T>> {
+        if !refs.iter().all(|value| self.is_valid(*value)) {
+            return None;
+        }
+
+        let mut order: Vec<usize> = (0..refs.len()).collect();
+        order.sort_by_key(|&pos| refs[pos]);
+        order.dedup_by_key(|pos| refs[*pos]);
+
+        let sorted = self.get_sorted_disjoint_mut(order.iter().map(|&pos| refs[pos]));
+        let mut values: Vec<(usize, &'a mut T)> = order.into_iter().zip(sorted).collect();
+        values.sort_unstable_by_key(|(pos, _)| *pos);
+        Some(values.into_iter().map(|(_, value)| value).collect())
+    }
+
+    /// Attempts to get mutable references to the values of two references at once.
+    ///
+    /// # Errors
+    /// Returns an error if either reference is invalid, or if both are equal.
+    pub fn try_get2_mut(
+        &mut self,
+        a: Ref<T>,
+        b: Ref<T>,
+    ) -> Result<(&mut T, &mut T), DisjointError> {
+        for value in [a, b] {
+            if !self.is_valid(value) {
+                return Err(DisjointError::Invalid(value.to_raw()));
+            }
+        }
+
+        if a == b {
+            return Err(DisjointError::Aliased(a.to_raw()));
+        }
+
+        let (head, tail) = self.raw.split_at_mut(a.max(b).to_raw() as usize);
+        let (low, high) = (&mut head[a.min(b).to_raw() as usize], &mut tail[0]);
+        if a < b {
+            Ok((low, high))
+        } else {
+            Ok((high, low))
+        }
+    }
+
+    /// Attempts to get a mutable reference to the value of `target` along with shared references
+    /// to the values of `others`.  Returns [`None`] if any reference is invalid, or if `target` is
+    /// one of `others`.
+    pub fn get_mut_and<'a, const N: usize>(
+        &'a mut self,
+        target: Ref<T>,
+        others: [Ref<T>; N],
+    ) -> Option<(&'a mut T, [&'a T; N])> {
+        if !self.is_valid(target) || !others.iter().all(|value| self.is_valid(*value)) {
+            return None;
+        }
+
+        if others.contains(&target) {
+            return None;
+        }
+
+        let target = target.to_raw() as usize;
+        let (before, rest) = self.raw.split_at_mut(target);
+        let (value, after) = rest.split_first_mut()?;
+        let (before, after): (&'a [T], &'a [T]) = (before, after);
+        let others = others.map(|other| match other.to_raw() as usize {
+            idx if idx < target => &before[idx],
+            idx => &after[idx - target - 1],
+        });
+
+        Some((value, others))
+    }
+
+    /// Calls the closure with a mutable reference to the value of each provided reference,
+    /// skipping invalid references, and returns the number of values updated.  A value is updated
+    /// once per occurrence of its reference.
+    pub fn update_each<F: FnMut(&mut T)>(&mut self, refs: &[Ref<T>], mut f: F) -> usize {
+        let mut updated = 0;
+        for value in refs {
+            if self.alive.contains(value.to_raw()) {
+                f(&mut self.raw[value.to_raw() as usize]);
+                updated += 1;
+            }
+        }
+
+        updated
+    }
+
+    /// Calls the closure with the value of the provided reference, returning its result, or
+    /// returns [`None`] if the reference was invalid.
+    #[inline]
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, value: Ref<T>, f: F) -> Option<R> {
+        self.try_get(value).map(f)
+    }
+
+    /// Calls the closure with a mutable reference to the value of the provided reference,
+    /// returning its result, or returns [`None`] if the reference was invalid.
+    #[inline]
+    pub fn with_mut<R, F: FnOnce(&mut T) -> R>(&mut self, value: Ref<T>, f: F) -> Option<R> {
+        self.try_get_mut(value).map(f)
+    }
+
+    /// Creates an empty [SlotArena] with the same configuration as this one.
+    #[inline]
+    fn empty_like(&self) -> Self {
+        Self {
+            policy: self.policy,
+            bound: self.bound,
+            version: self.version,
+            grow_hook: self.grow_hook.clone(),
+            ..Self::new()
+        }
+    }
+
+    /// Returns mutable references to the values of the provided references, which must be in
+    /// bounds and strictly increasing.
+    fn get_sorted_disjoint_mut(&mut self, refs: impl Iterator<Item = Ref<T>>) -> Vec<&mut T> {
+        let mut rest = self.raw.as_mut_slice();
+        let mut offset = 0;
+        let mut values = Vec::with_capacity(refs.size_hint().0);
+        for value in refs {
+            let idx = value.to_raw() as usize;
+            let (head, tail) = std::mem::take(&mut rest).split_at_mut(idx - offset + 1);
+            values.extend(head.last_mut());
+            rest = tail;
+            offset = idx + 1;
+        }
+
+        values
+    }
+
+    /// Returns the number of slots in the memory block, which always fits in a `u32`.
+    #[inline]
+    fn slot_count(&self) -> u32 {
+        self.raw.len() as u32
+    }
+
+    /// Pushes a value into a new slot at the end of the memory block.
+    ///
+    /// # Errors
+    /// Returns an error if the memory block already holds [SlotArena::bound] slots.
+    #[inline]
+    fn push(&mut self, value: T) -> Result<Ref<T>, ArenaError> {
+        let idx = Ref::from_raw(index_from_usize(self.raw.len())?);
+        if idx.to_raw() >= self.bound {
+            return Err(ArenaError::CapacityExceeded(self.bound));
+        }
+
+        let capacity = self.raw.capacity();
+        self.raw.push(value);
+        self.alive.insert(idx.to_raw());
+        self.notify_grow(capacity);
+        Ok(idx)
+    }
+
+    /// Invokes the grow hook if the capacity of the memory block changed from the provided one.
+    #[inline]
+    fn notify_grow(&self, capacity: usize) {
+        if let Some(hook) = self.grow_hook.get() {
+            if self.raw.capacity() != capacity {
+                hook(capacity, self.raw.capacity());
+            }
+        }
+    }
+
+    /// Compacts the [SlotArena] if auto-compaction is enabled and its threshold is exceeded.
+    fn maybe_auto_compact(&mut self) {
+        match self.auto_compact.get() {
+            Some(policy) if self.fragmentation() > policy.threshold => {}
+            _ => return,
+        }
+
+        let auto_compact = self.auto_compact.clone();
+        if let Some(policy) = auto_compact.get() {
+            let remap = self.defragment();
+            (policy.fixup)(remap.cast());
+        }
+    }
+
+    /// Takes the next freed slot to reuse according to the [ReusePolicy].
+    #[inline]
+    fn pop_free(&mut self) -> Option<Ref<T>> {
+        match self.policy {
+            ReusePolicy::Lifo => self.free.pop_back(),
+            ReusePolicy::Fifo => self.free.pop_front(),
+        }
+    }
+
+    /// Panics in debug builds if the provided reference has been freed or reserved.
+    #[inline]
+    #[track_caller]
+    fn debug_assert_not_freed(&self, value: Ref<T>) {
+        let idx = value.to_raw();
+        debug_assert!(!self.reserved.contains(idx), "invalid Ref #{idx}: slot is reserved");
+        debug_assert!(
+            self.alive.contains(idx) || idx as usize >= self.raw.len(),
+            "invalid Ref #{idx}: slot is freed"
+        );
+    }
+
+    /// Returns an error if a slot reserved by [SlotArena::reserve_slot] is not filled.
+    pub(crate) fn check_filled(&self) -> Result<(), ArenaError> {
+        if self.reserved_len == 0 {
+            return Ok(());
+        }
+
+        match (0..self.slot_count()).find(|idx| self.reserved.contains(*idx)) {
+            Some(idx) => Err(ArenaError::UnfilledReservation(idx)),
+            None => Ok(()),
+        }
+    }
+
     /// Returns an iterator through the alive items in the [SlotArena].
     pub fn iter(&self) -> impl Iterator<Item = (Ref<T>, &T)> {
-        self.raw
-            .iter()
-            .enumerate()
-            .map(|(idx, item)| (Ref::from_raw(idx as u32), item))
-            .filter(|(idx, _)| !self.free.contains(&idx))
+        (0..)
+            .zip(self.raw.iter())
+            .map(|(idx, item)| (Ref::from_raw(idx), item))
+            .filter(|(idx, _)| self.alive.contains(idx.to_raw()))
     }
 
     /// Returns an iterator through the alive items in the [SlotArena].
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (Ref<T>, &mut T)> {
-        self.raw
-            .iter_mut()
-            .enumerate()
-            .map(|(idx, item)| (Ref::from_raw(idx as u32), item))
-            .filter(|(idx, _)| !self.free.contains(&idx))
+        (0..)
+            .zip(self.raw.iter_mut())
+            .map(|(idx, item)| (Ref::from_raw(idx), item))
+            .filter(|(idx, _)| self.alive.contains(idx.to_raw()))
+    }
+
+    /// Returns an iterator through every slot of the memory block in index order, as raw indices
+    /// along with a mutable reference to the item for alive slots, or [`None`] for the others.
+    pub fn iter_raw_mut(&mut self) -> impl Iterator<Item = (u32, Option<&mut T>)> {
+        (0..)
+            .zip(self.raw.iter_mut())
+            .map(|(idx, item)| (idx, self.alive.contains(idx).then_some(item)))
+    }
+
+    /// Returns an iterator through the maximal runs of contiguous freed slots in the [SlotArena],
+    /// in index order, as `(first slot, run length)` pairs.
+    pub fn free_runs(&self) -> impl Iterator<Item = (Ref<T>, u32)> + '_ {
+        let mut free = BitSet::with_capacity(self.slot_count());
+        for idx in &self.free {
+            free.insert(idx.to_raw());
+        }
+
+        let len = self.slot_count();
+        let mut idx = 0;
+        std::iter::from_fn(move || {
+            while idx < len && !free.contains(idx) {
+                idx += 1;
+            }
+
+            if idx == len {
+                return None;
+            }
+
+            let start = idx;
+            while idx < len && free.contains(idx) {
+                idx += 1;
+            }
+
+            Some((Ref::from_raw(start), idx - start))
+        })
+    }
+
+    /// Returns the items of the [SlotArena] as a slice, indexed by [Ref::to_raw], or [`None`] if
+    /// any slot is freed or reserved.
+    #[inline]
+    pub fn as_slice(&self) -> Option<&[T]> {
+        if self.len() == self.raw.len() {
+            Some(&self.raw)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator through the alive items in the [SlotArena], gathered in chunks of `n`
+    /// items (the last chunk may be shorter).
+    ///
+    /// When the [SlotArena] has no freed or reserved slot, `as_slice().unwrap().chunks(n)` yields
+    /// the same chunks as contiguous slices, without allocating.
+    ///
+    /// # Panics
+    /// Panics if `n` is `0`.
+    pub fn chunks_live(&self, n: usize) -> impl Iterator<Item = Vec<(Ref<T>, &T)>> {
+        assert!(n != 0, "chunk size must be non-zero");
+        let mut items = self.iter();
+        std::iter::from_fn(move || {
+            let chunk: Vec<_> = items.by_ref().take(n).collect();
+            (!chunk.is_empty()).then_some(chunk)
+        })
+    }
+
+    /// Clears the provided buffer and fills it with the [Ref]s to the alive items in the
+    /// [SlotArena], in index order, reusing the buffer's capacity.
+    pub fn collect_refs_into(&self, buf: &mut Vec<Ref<T>>) {
+        buf.clear();
+        buf.extend(self.iter().map(|(idx, _)| idx));
+    }
+
+    /// Returns the number of alive items in the [SlotArena] for which the predicate returns `true`.
+    pub fn count_matching<F: FnMut(&T) -> bool>(&self, mut f: F) -> usize {
+        self.iter().filter(|(_, item)| f(item)).count()
+    }
+
+    /// Returns an iterator moving the alive items out of the [SlotArena] in index order.  The
+    /// [SlotArena] is left empty, but keeps its capacity for reuse; the items not yet yielded are
+    /// dropped along with the iterator.
+    ///
+    /// Use the [IntoIterator] implementation to consume the [SlotArena] instead.
+    pub fn drain_into_iter(&mut self) -> impl Iterator<Item = (Ref<T>, T)> + '_ {
+        let alive = std::mem::take(&mut self.alive);
+        self.free.clear();
+        self.reserved = BitSet::new();
+        self.reserved_len = 0;
+        (0..)
+            .zip(self.raw.drain(..))
+            .filter(move |(idx, _)| alive.contains(*idx))
+            .map(|(idx, item)| (Ref::from_raw(idx), item))
+    }
+
+    /// Sorts the alive items of the [SlotArena] with the provided comparator, packing them at the
+    /// start of the memory block in sorted order.  The sort is stable.
+    ///
+    /// Reserved slots are kept after the sorted items, in index order.  Every item and reserved
+    /// slot may move, so the returned [RefRemap] must be used to update outstanding [Ref]s.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut f: F) -> RefRemap<T> {
+        let alive = std::mem::take(&mut self.alive);
+        let reserved = std::mem::take(&mut self.reserved);
+        let reserved_len = std::mem::take(&mut self.reserved_len);
+        self.free.clear();
+
+        let (mut items, placeholders): (Vec<_>, Vec<_>) = (0..)
+            .zip(self.raw.drain(..))
+            .filter(|(idx, _)| alive.contains(*idx) || reserved.contains(*idx))
+            .map(|(idx, item)| (Ref::from_raw(idx), item))
+            .partition(|(idx, _)| alive.contains(idx.to_raw()));
+        items.sort_by(|(_, a), (_, b)| f(a, b));
+
+        let sorted = items.len() as u32;
+        let mut remap = RefRemap::new();
+        for (idx, (old, item)) in (0..).zip(items.into_iter().chain(placeholders)) {
+            self.raw.push(item);
+            remap.insert(old, Ref::from_raw(idx));
+        }
+
+        self.alive = BitSet::full(sorted);
+        for idx in sorted..self.slot_count() {
+            self.reserved.insert(idx);
+        }
+        self.reserved_len = reserved_len;
+        remap
+    }
+
+    /// Drops every alive item, in the order given by the provided comparator, leaving the
+    /// [SlotArena] empty.  The stale values of freed slots are dropped first, in index order.
+    ///
+    /// The [SlotArena] is emptied before any value is dropped: if the comparator or a destructor
+    /// panics, it stays empty and the remaining values are dropped while unwinding.
+    pub fn drop_in_order<F: FnMut(&T, &T) -> Ordering>(&mut self, cmp: F) {
+        let mut items: Vec<T> = self.drain_into_iter().map(|(_, item)| item).collect();
+        items.sort_by(cmp);
+        items.into_iter().for_each(drop);
+    }
+
+    /// Consumes the [SlotArena], distributing its alive items into two new [SlotArena]s: items for
+    /// which the predicate returns `true` go into the first, the rest into the second.
+    ///
+    /// Items are inserted into the new arenas in index order, so their [Ref]s are rebased: a [Ref]
+    /// into the original [SlotArena] should not be used with either of the returned arenas.
+    pub fn partition<F: FnMut(Ref<T>, &T) -> bool>(self, mut f: F) -> (SlotArena<T>, SlotArena<T>) {
+        let mut matching = self.empty_like();
+        let mut rest = self.empty_like();
+        for (idx, item) in self {
+            if f(idx, &item) {
+                matching.insert(item);
+            } else {
+                rest.insert(item);
+            }
+        }
+
+        (matching, rest)
+    }
+}
+
+impl SlotArena<()> {
+    /// Allocates a new ID, reusing freed IDs first.  Equivalent to `insert(())`.
+    ///
+    /// # Panics
+    /// Panics if the number of IDs in this [SlotArena] exceeds [SlotArena::bound].
+    #[inline]
+    pub fn alloc_id(&mut self) -> Ref<()> {
+        self.insert(())
+    }
+
+    /// Frees the provided ID, so it can be reused.  Equivalent to `free(id)`.
+    #[inline]
+    pub fn free_id(&mut self, id: Ref<()>) {
+        self.free(id);
+    }
+}
+
+impl<T: Deref> SlotArena<T> {
+    /// Returns a non-opaque reference to the target of the provided value, such as the `dyn Trait`
+    /// of a `SlotArena<Box<dyn Trait>>`.
+    ///
+    /// # Panics
+    /// See [SlotArena::get].
+    #[inline]
+    #[track_caller]
+    pub fn get_deref(&self, value: Ref<T>) -> &T::Target {
+        self.get(value)
+    }
+}
+
+impl<T: DerefMut> SlotArena<T> {
+    /// Returns a mutable non-opaque reference to the target of the provided value, such as the
+    /// `dyn Trait` of a `SlotArena<Box<dyn Trait>>`.
+    ///
+    /// # Panics
+    /// See [SlotArena::get_mut].
+    #[inline]
+    #[track_caller]
+    pub fn get_deref_mut(&mut self, value: Ref<T>) -> &mut T::Target {
+        self.get_mut(value)
+    }
+}
+
+impl<T: PartialEq> SlotArena<T> {
+    /// Returns a [Ref] to an alive item equal to the provided value, inserting the value if there
+    /// is none.
+    ///
+    /// This scans every alive item, so it takes `O(n)` time: it is meant for interning into small
+    /// arenas.  For large arenas, keep a [HashMap] from values to [Ref]s alongside the [SlotArena]
+    /// instead.
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[track_caller]
+    pub fn insert_unique(&mut self, value: T) -> Ref<T> {
+        let existing = self.iter().find(|(_, item)| **item == value);
+        match existing.map(|(idx, _)| idx) {
+            Some(idx) => idx,
+            None => self.insert(value),
+        }
+    }
+}
+
+impl<T: Copy> SlotArena<T> {
+    /// Returns a copy of the provided value.
+    ///
+    /// # Panics
+    /// See [SlotArena::get].
+    #[inline]
+    #[track_caller]
+    pub fn get_copy(&self, value: Ref<T>) -> T {
+        *self.get(value)
+    }
+
+    /// Attempts to get a copy of the provided value, returns [`None`] if the reference was
+    /// invalid.
+    #[inline]
+    pub fn try_get_copy(&self, value: Ref<T>) -> Option<T> {
+        self.try_get(value).copied()
+    }
+}
+
+impl<T: Default> SlotArena<T> {
+    /// Reserves a slot holding a default value until it is filled, see
+    /// [SlotArena::reserve_slot_with].
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[inline]
+    #[track_caller]
+    pub fn reserve_slot(&mut self) -> Ref<T> {
+        self.reserve_slot_with(T::default())
+    }
+
+    /// Inserts a default value into the [SlotArena], returning a [Ref] to it along with a mutable
+    /// reference to the inserted value.
+    ///
+    /// # Panics
+    /// Panics if the number of items in this [SlotArena] exceeds [SlotArena::bound].
+    #[inline]
+    #[track_caller]
+    pub fn get_or_insert_default(&mut self) -> (Ref<T>, &mut T) {
+        self.insert_mut(T::default())
+    }
+}
+
+impl<T: Eq + Hash> SlotArena<T> {
+    /// Returns `true` if both [SlotArena]s hold the same alive values, counted with multiplicity,
+    /// regardless of the slots they occupy.
+    ///
+    /// Unlike `==`, which compares the memory blocks slot by slot, this ignores [Ref]s entirely.
+    pub fn values_eq(&self, other: &SlotArena<T>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        let mut counts: HashMap<&T, usize> = HashMap::new();
+        for (_, item) in self.iter() {
+            *counts.entry(item).or_default() += 1;
+        }
+
+        for (_, item) in other.iter() {
+            match counts.get_mut(item) {
+                Some(0) | None => return false,
+                Some(count) => *count -= 1,
+            }
+        }
+
+        true
+    }
+}
+
+impl<T: Clone> SlotArena<T> {
+    /// Clones the alive items of the [SlotArena] into a new, unfragmented [SlotArena], returning it
+    /// along with a [RefRemap] from each [Ref] into `self` to the matching [Ref] into the clone.
+    ///
+    /// Unlike [Clone], the layout of the memory block is not preserved.
+    pub fn compact_clone(&self) -> (SlotArena<T>, RefRemap<T>) {
+        let mut clone = self.empty_like();
+
+        let mut remap = RefRemap::new();
+        for (idx, item) in self.iter() {
+            remap.insert(idx, clone.insert(item.clone()));
+        }
+
+        (clone, remap)
+    }
+}
+
+impl<T: Clone> Clone for SlotArena<T> {
+    /// Clones the [SlotArena], without its auto-compaction (see [SlotArena::set_auto_compact]).
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            free: self.free.clone(),
+            alive: self.alive.clone(),
+            reserved: self.reserved.clone(),
+            reserved_len: self.reserved_len,
+            policy: self.policy,
+            bound: self.bound,
+            version: self.version,
+            grow_hook: self.grow_hook.clone(),
+            auto_compact: Hook::none(),
+        }
+    }
+}
+
+impl<T> Default for SlotArena<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -147,3 +1238,12 @@ impl<T: Debug> Debug for SlotArena<T> {
         f.debug_map().entries(self.iter()).finish()
     }
 }
+
+/// Converts a slot index to a `u32`, failing if it is past the last slot a [SlotArena] can hold.
+#[inline]
+fn index_from_usize(idx: usize) -> Result<u32, ArenaError> {
+    match u32::try_from(idx) {
+        Ok(idx) if idx < u32::MAX => Ok(idx),
+        _ => Err(ArenaError::CapacityExceeded(u32::MAX)),
+    }
+}
@@ -0,0 +1,62 @@
+/// Declares a transparent newtype around a [Ref](crate::Ref), for strongly-typed handles at
+/// domain boundaries.
+///
+/// The generated type is `Copy`, `Debug`, `Eq`, `Ord` and `Hash`, and converts to and from the
+/// wrapped [Ref](crate::Ref) with [From].
+///
+/// ```
+/// use slot_arena::{declare_ref, SlotArena};
+///
+/// struct Node;
+///
+/// declare_ref!(pub NodeId => Node);
+///
+/// let mut nodes = SlotArena::new();
+/// let id = NodeId::from(nodes.insert(Node));
+/// let _node: &Node = nodes.get(id.into());
+/// ```
+#[macro_export]
+macro_rules! declare_ref {
+    ($($(#[$meta:meta])* $vis:vis $name:ident => $ty:ty);+ $(;)?) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[repr(transparent)]
+            $vis struct $name($crate::Ref<$ty>);
+
+            impl $name {
+                /// Creates a handle from a raw index value.
+                #[inline]
+                pub const fn from_raw(idx: u32) -> Self {
+                    Self($crate::Ref::from_raw(idx))
+                }
+
+                /// Returns the raw index of the handle.
+                #[inline]
+                pub const fn to_raw(self) -> u32 {
+                    self.0.to_raw()
+                }
+
+                /// Returns the wrapped `Ref`.
+                #[inline]
+                pub const fn to_ref(self) -> $crate::Ref<$ty> {
+                    self.0
+                }
+            }
+
+            impl ::core::convert::From<$crate::Ref<$ty>> for $name {
+                #[inline]
+                fn from(value: $crate::Ref<$ty>) -> Self {
+                    Self(value)
+                }
+            }
+
+            impl ::core::convert::From<$name> for $crate::Ref<$ty> {
+                #[inline]
+                fn from(value: $name) -> Self {
+                    value.0
+                }
+            }
+        )+
+    };
+}
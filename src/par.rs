@@ -0,0 +1,19 @@
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::SlotArena;
+
+impl<T: Send> SlotArena<T> {
+    /// Creates a [SlotArena] from a parallel iterator, producing its items on the rayon thread
+    /// pool.  Items are stored in the order of the iterator, so the `i`th item gets the
+    /// [Ref](crate::Ref) with raw index `i`.
+    ///
+    /// # Panics
+    /// Panics if the iterator yields more than `u32::MAX` items.
+    #[track_caller]
+    pub fn par_build<I: IntoParallelIterator<Item = T>>(iter: I) -> Self {
+        match SlotArena::from_parts(iter.into_par_iter().collect(), Vec::new()) {
+            Ok(arena) => arena,
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
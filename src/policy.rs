@@ -0,0 +1,32 @@
+/// Determines which freed slot a [SlotArena](crate::SlotArena) reuses on insertion.
+///
+/// The order is guaranteed, so the [Ref](crate::Ref)s returned by a sequence of insertions and
+/// frees are fully deterministic:
+///
+/// ```
+/// use slot_arena::{ReusePolicy, SlotArena};
+///
+/// let mut arena = SlotArena::new();
+/// let a = arena.insert("a");
+/// let b = arena.insert("b");
+/// arena.free(a);
+/// arena.free(b);
+/// assert_eq!(arena.insert("x"), b);
+///
+/// let mut arena = SlotArena::new();
+/// arena.set_reuse_policy(ReusePolicy::Fifo);
+/// let a = arena.insert("a");
+/// let b = arena.insert("b");
+/// arena.free(a);
+/// arena.free(b);
+/// assert_eq!(arena.insert("x"), a);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReusePolicy {
+    /// The most recently freed slot is reused first.
+    #[default]
+    Lifo,
+    /// The least recently freed slot is reused first.
+    Fifo,
+}
@@ -1,6 +1,7 @@
 use std::{
     fmt::{Debug, Display},
     hash::Hash,
+    iter::FusedIterator,
     marker::PhantomData,
 };
 
@@ -27,15 +28,26 @@ impl<T> Ref<T> {
     pub const fn to_raw(self) -> u32 {
         self.idx
     }
+
+    /// Returns the slot index of the [Ref], to be stored compactly and turned back into a [Ref]
+    /// with [SlotArena::current_ref](crate::SlotArena::current_ref).
+    #[inline]
+    pub const fn raw_index(self) -> u32 {
+        self.idx
+    }
+
+    /// Returns the value the [Ref] is hashed as, which does not depend on `T`: a [Ref] hashes
+    /// identically to its raw `u32` index.
+    #[inline]
+    pub const fn hash_raw(self) -> u32 {
+        self.idx
+    }
 }
 
 impl<T> Clone for Ref<T> {
     #[inline]
     fn clone(&self) -> Self {
-        Self {
-            marker: PhantomData,
-            idx: self.idx,
-        }
+        *self
     }
 }
 
@@ -54,8 +66,9 @@ impl<T> Display for Ref<T> {
 }
 
 impl<T> Hash for Ref<T> {
+    #[inline]
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write_u32(self.idx);
+        self.hash_raw().hash(state);
     }
 }
 
@@ -69,7 +82,7 @@ impl<T> Eq for Ref<T> {}
 
 impl<T> PartialOrd for Ref<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.idx.partial_cmp(&other.idx)
+        Some(self.cmp(other))
     }
 }
 
@@ -78,3 +91,77 @@ impl<T> Ord for Ref<T> {
         self.idx.cmp(&other.idx)
     }
 }
+
+/// A contiguous range of [Ref]s, yielded in increasing index order.
+pub struct RefRange<T> {
+    marker: PhantomData<T>,
+    start: u32,
+    end: u32,
+}
+
+impl<T> RefRange<T> {
+    /// Creates a [RefRange] from `start` (inclusive) to `end` (exclusive).
+    #[inline]
+    pub(crate) const fn new(start: u32, end: u32) -> Self {
+        Self {
+            marker: PhantomData,
+            start,
+            end,
+        }
+    }
+}
+
+impl<T> Clone for RefRange<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.start, self.end)
+    }
+}
+
+impl<T> Debug for RefRange<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RefRange::<{}>({}..{})",
+            std::any::type_name::<T>(),
+            self.start,
+            self.end
+        )
+    }
+}
+
+impl<T> Iterator for RefRange<T> {
+    type Item = Ref<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            self.start += 1;
+            Some(Ref::from_raw(self.start - 1))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.start) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for RefRange<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(Ref::from_raw(self.end))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for RefRange<T> {}
+
+impl<T> FusedIterator for RefRange<T> {}
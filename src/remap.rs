@@ -0,0 +1,105 @@
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
+
+use crate::Ref;
+
+/// A table mapping old [Ref]s to new ones, returned by operations that move the items of a
+/// [SlotArena](crate::SlotArena) around.  It holds every [Ref] still valid after the operation,
+/// including those that did not move.
+pub struct RefRemap<T> {
+    marker: PhantomData<Ref<T>>,
+    map: HashMap<u32, u32>,
+}
+
+impl<T> RefRemap<T> {
+    /// Creates an empty [RefRemap].
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            marker: PhantomData,
+            map: HashMap::new(),
+        }
+    }
+
+    /// Reinterprets the table for another item type, so that it can cross the type-erased
+    /// auto-compaction callback without making [SlotArena](crate::SlotArena) invariant in `T`.
+    #[inline]
+    pub(crate) fn cast<U>(self) -> RefRemap<U> {
+        RefRemap {
+            marker: PhantomData,
+            map: self.map,
+        }
+    }
+
+    /// Records that `old` has moved to `new`.
+    #[inline]
+    pub(crate) fn insert(&mut self, old: Ref<T>, new: Ref<T>) {
+        self.map.insert(old.to_raw(), new.to_raw());
+    }
+
+    /// Returns the new [Ref] for the provided old one, or [`None`] if it is no longer valid.
+    #[inline]
+    pub fn translate(&self, value: Ref<T>) -> Option<Ref<T>> {
+        self.map.get(&value.to_raw()).copied().map(Ref::from_raw)
+    }
+
+    /// Returns the new [Ref] for the provided old one, or the old one if it is not in the table.
+    #[inline]
+    pub fn translate_or_keep(&self, value: Ref<T>) -> Ref<T> {
+        self.translate(value).unwrap_or(value)
+    }
+
+    /// Returns the number of [Ref]s in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the table is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator through the `(old, new)` pairs of the table, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Ref<T>, Ref<T>)> + '_ {
+        self.map
+            .iter()
+            .map(|(old, new)| (Ref::from_raw(*old), Ref::from_raw(*new)))
+    }
+
+    /// Converts the table into a [HashMap] from old to new [Ref]s, allocating a new one.
+    pub fn into_inner(self) -> HashMap<Ref<T>, Ref<T>> {
+        self.iter().collect()
+    }
+}
+
+impl<T> Clone for RefRemap<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            marker: PhantomData,
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T> Default for RefRemap<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for RefRemap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for RefRemap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T> Eq for RefRemap<T> {}
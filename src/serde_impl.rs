@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ArenaError, Ref, ReusePolicy, SlotArena};
+
+/// The serialized representation of a [SlotArena].
+#[derive(Serialize)]
+struct SlotArenaRepr<'a, T> {
+    version: u32,
+    policy: ReusePolicy,
+    bound: u32,
+    raw: &'a [T],
+    free: &'a VecDeque<Ref<T>>,
+}
+
+/// The deserialized representation of a [SlotArena].
+#[derive(Deserialize)]
+struct SlotArenaOwnedRepr<T> {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    policy: ReusePolicy,
+    #[serde(default = "default_bound")]
+    bound: u32,
+    raw: Vec<T>,
+    free: Vec<Ref<T>>,
+}
+
+/// The bound of [SlotArena]s serialized before it was recorded.
+fn default_bound() -> u32 {
+    u32::MAX
+}
+
+impl<T> Serialize for Ref<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.to_raw())
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Ref<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Ref::from_raw)
+    }
+}
+
+impl<T: Serialize> Serialize for SlotArena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.check_filled().map_err(ser::Error::custom)?;
+        SlotArenaRepr {
+            version: self.version,
+            policy: self.policy,
+            bound: self.bound,
+            raw: &self.raw,
+            free: &self.free,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SlotArena<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SlotArenaOwnedRepr::deserialize(deserializer)?;
+        if repr.raw.len() > repr.bound as usize {
+            return Err(de::Error::custom(ArenaError::CapacityExceeded(repr.bound)));
+        }
+
+        let mut arena = SlotArena::from_parts(repr.raw, repr.free).map_err(de::Error::custom)?;
+        arena.version = repr.version;
+        arena.policy = repr.policy;
+        arena.bound = repr.bound;
+        Ok(arena)
+    }
+}
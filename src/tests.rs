@@ -0,0 +1,455 @@
+use std::{
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::*;
+
+#[test]
+fn remove_all_compacts_once() {
+    let mut arena = SlotArena::new();
+    for value in 0..10 {
+        arena.insert(value);
+    }
+
+    let fixups = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&fixups);
+    arena
+        .set_auto_compact(0.1, move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+
+    assert_eq!(arena.remove_all(|_, value| value % 2 == 0), 5);
+    assert_eq!(fixups.load(Ordering::Relaxed), 1);
+    assert_eq!(arena.iter().map(|(_, value)| *value).collect::<Vec<_>>(), [1, 3, 5, 7, 9]);
+    arena.assert_invariants();
+}
+
+#[test]
+fn covariant_in_item_type() {
+    fn shorten<'a>(arena: SlotArena<&'static str>) -> SlotArena<&'a str> {
+        arena
+    }
+
+    let mut arena = SlotArena::new();
+    arena.set_auto_compact(0.5, |_: &RefRemap<&str>| {}).unwrap();
+    let idx = arena.insert("static");
+    assert_eq!(*shorten(arena).get(idx), "static");
+}
+
+#[test]
+fn unwind_safe_with_hooks() {
+    fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>(_: &T) {}
+    fn assert_ref_unwind_safe<T: RefUnwindSafe>(_: &T) {}
+
+    let mut arena = SlotArena::<u8>::new();
+    arena.set_grow_hook(|_, _| {});
+    arena.set_auto_compact(0.5, |_| {}).unwrap();
+    assert_unwind_safe(&arena);
+    assert_ref_unwind_safe(&SlotArena::<u8>::builder().auto_compact(0.5, |_| {}));
+}
+
+#[test]
+fn equality_ignores_bitmap_history() {
+    let prefilled = SlotArena::with_prefilled(1, || 7);
+    let assembled = SlotArena::from_parts(vec![7], vec![Ref::from_raw(0)]).unwrap();
+    assert!(prefilled == assembled);
+    assert_eq!(prefilled.cmp(&assembled), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn sort_by_keeps_reservations() {
+    let mut arena = SlotArena::new();
+    let three = arena.insert(3);
+    let reserved = arena.reserve_slot();
+    let one = arena.insert(1);
+
+    let remap = arena.sort_by(Ord::cmp);
+    assert_eq!(remap.translate(one), Some(Ref::from_raw(0)));
+    assert_eq!(remap.translate(three), Some(Ref::from_raw(1)));
+    assert_eq!(remap.translate(reserved), Some(Ref::from_raw(2)));
+    arena.assert_invariants();
+
+    arena.fill(remap.translate_or_keep(reserved), 2);
+    assert_eq!(arena.iter().map(|(_, value)| *value).collect::<Vec<_>>(), [1, 3, 2]);
+    arena.assert_invariants();
+}
+
+#[test]
+fn free_cancels_reservation() {
+    let mut arena = SlotArena::<u32>::new();
+    let reserved = arena.reserve_slot();
+    arena.free(reserved);
+    assert_eq!(arena.free_count(), 1);
+    arena.assert_invariants();
+
+    assert_eq!(arena.insert(5), reserved);
+    arena.assert_invariants();
+}
+
+#[test]
+#[should_panic(expected = "reserved slot #1 is not filled")]
+fn into_parts_refuses_reservations() {
+    let mut arena = SlotArena::new();
+    arena.insert(0);
+    arena.reserve_slot();
+    arena.into_parts();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "invalid Ref #0: slot is reserved")]
+fn get_reserved_slot() {
+    let mut arena = SlotArena::<u32>::new();
+    let reserved = arena.reserve_slot();
+    arena.get(reserved);
+}
+
+#[test]
+fn invalid_threshold() {
+    let mut arena = SlotArena::<u8>::new();
+    for threshold in [f64::NAN, -0.5] {
+        assert_eq!(arena.set_auto_compact(threshold, |_| {}), Err(ArenaError::InvalidThreshold));
+        let builder = SlotArena::<u8>::builder().auto_compact(threshold, |_| {});
+        assert_eq!(builder.build().err(), Some(ArenaError::InvalidThreshold));
+    }
+}
+
+#[test]
+fn auto_compact_fixup_remaps_outstanding_refs() {
+    let mut arena = SlotArena::new();
+    let refs: Vec<_> = (0..8).map(|value| arena.insert(value)).collect();
+
+    let remaps = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = Arc::clone(&remaps);
+    arena
+        .set_auto_compact(0.3, move |remap| sink.lock().unwrap().push(remap.clone()))
+        .unwrap();
+
+    arena.free(refs[0]);
+    arena.free(refs[1]);
+    assert!(remaps.lock().unwrap().is_empty());
+    arena.free(refs[2]);
+    arena.assert_invariants();
+
+    let remaps = remaps.lock().unwrap();
+    assert_eq!(remaps.len(), 1);
+    assert_eq!(arena.free_count(), 0);
+    for (value, idx) in (3..8).zip(&refs[3..]) {
+        assert_eq!(*arena.get(remaps[0].translate_or_keep(*idx)), value);
+    }
+}
+
+#[test]
+fn reservation_accounting() {
+    let mut arena = SlotArena::<u32>::new();
+    let a = arena.insert(1);
+    let reserved = arena.reserve_slot();
+    let b = arena.insert(2);
+    let late = arena.reserve_slot();
+    assert_eq!((arena.len(), arena.free_count()), (2, 0));
+    arena.assert_invariants();
+
+    arena.fill(reserved, 3);
+    assert_eq!(arena.len(), 3);
+    assert!(arena.is_valid(reserved) && !arena.is_valid(late));
+    arena.assert_invariants();
+
+    arena.free(a);
+    let remap = arena.defragment();
+    let (b, late) = (remap.translate_or_keep(b), remap.translate_or_keep(late));
+    assert_eq!((arena.len(), arena.free_count()), (2, 0));
+    assert!(!arena.is_valid(late));
+    arena.assert_invariants();
+
+    assert_eq!(arena.truncate(1), [b, late]);
+    assert_eq!(arena.len(), 1);
+    arena.assert_invariants();
+}
+
+#[test]
+fn disjoint_access_order() {
+    let mut arena = SlotArena::new();
+    let refs: Vec<_> = (0..4).map(|value| arena.insert(value)).collect();
+
+    let values = arena.get_disjoint_mut_slice(&[refs[3], refs[0], refs[2]]).unwrap();
+    assert_eq!(values.iter().map(|value| **value).collect::<Vec<_>>(), [3, 0, 2]);
+    assert!(arena.get_disjoint_mut_slice(&[refs[1], refs[1]]).is_none());
+
+    let values = arena.get_deduped_mut(&[refs[2], refs[1], refs[2], refs[3]]).unwrap();
+    assert_eq!(values.iter().map(|value| **value).collect::<Vec<_>>(), [2, 1, 3]);
+
+    arena.free(refs[1]);
+    assert!(arena.get_disjoint_mut_slice(&[refs[0], refs[1]]).is_none());
+    assert!(arena.get_deduped_mut(&[refs[1]]).is_none());
+}
+
+#[test]
+fn insert_into_default() {
+    #[derive(Default)]
+    struct Owner {
+        arena: SlotArena<u32>,
+    }
+
+    let mut owner = Owner::default();
+    assert_eq!(owner.arena.bound(), u32::MAX);
+    let idx = owner.arena.insert(1);
+    assert_eq!(owner.arena.try_insert(2), Some(Ref::from_raw(1)));
+    assert_eq!(*owner.arena.get(idx), 1);
+}
+
+#[test]
+fn clone_drops_auto_compaction() {
+    let mut arena = SlotArena::new();
+    let refs: Vec<_> = (0..4).map(|value| arena.insert(value)).collect();
+
+    let fixups = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&fixups);
+    arena
+        .set_auto_compact(0.1, move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+
+    let mut clone = arena.clone();
+    clone.free(refs[0]);
+    assert_eq!(fixups.load(Ordering::Relaxed), 0);
+    assert_eq!(clone.free_count(), 1);
+
+    arena.free(refs[0]);
+    assert_eq!(fixups.load(Ordering::Relaxed), 1);
+    assert_eq!(arena.free_count(), 0);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_keeps_bound() {
+    let mut arena = SlotArena::builder().bounded(2).build().unwrap();
+    arena.insert(1);
+    let json = serde_json::to_string(&arena).unwrap();
+    let back: SlotArena<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.bound(), 2);
+    assert!(back == arena);
+
+    let over = r#"{"bound":1,"raw":[1,2],"free":[]}"#;
+    let err = serde_json::from_str::<SlotArena<u32>>(over).err().unwrap();
+    assert!(err.to_string().contains("at most 1 slots"));
+
+    let legacy: SlotArena<u32> = serde_json::from_str(r#"{"raw":[1],"free":[]}"#).unwrap();
+    assert_eq!(legacy.bound(), u32::MAX);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_keeps_version_and_policy() {
+    let mut arena = SlotArena::builder().reuse_policy(ReusePolicy::Fifo).build().unwrap();
+    arena.set_version(3);
+    let refs: Vec<_> = (0..4).map(|value| arena.insert(value)).collect();
+    arena.free(refs[2]);
+    arena.free(refs[0]);
+
+    let json = serde_json::to_string(&arena).unwrap();
+    let mut back: SlotArena<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.version(), 3);
+    assert_eq!(back.reuse_policy(), ReusePolicy::Fifo);
+    assert_eq!(back.preview_inserts(2), [refs[2], refs[0]]);
+    assert_eq!(back.insert(4), refs[2]);
+    assert_eq!(back.insert(5), refs[0]);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn par_build_keeps_order() {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    let arena = SlotArena::par_build((0..1000u32).into_par_iter().map(|value| value * 2));
+    assert_eq!(arena.len(), 1000);
+    assert_eq!(*arena.get(Ref::from_raw(500)), 1000);
+    assert!(arena.iter().all(|(idx, value)| *value == idx.to_raw() * 2));
+    arena.assert_invariants();
+}
+
+#[test]
+fn defragment_remaps_every_valid_ref() {
+    let mut arena = SlotArena::new();
+    let refs: Vec<_> = (0..4).map(|value| arena.insert(value)).collect();
+    arena.free(refs[1]);
+
+    let remap = arena.defragment();
+    assert_eq!(remap.len(), 3);
+    assert_eq!(remap.translate(refs[0]), Some(refs[0]));
+    assert_eq!(remap.translate(refs[1]), None);
+    assert_eq!(remap.translate(refs[3]), Some(Ref::from_raw(2)));
+    arena.assert_invariants();
+}
+
+#[test]
+fn reserve_slot_without_default() {
+    trait Node {
+        fn next(&self) -> Option<Ref<Box<dyn Node>>>;
+    }
+
+    struct Link(Option<Ref<Box<dyn Node>>>);
+
+    impl Node for Link {
+        fn next(&self) -> Option<Ref<Box<dyn Node>>> {
+            self.0
+        }
+    }
+
+    let mut arena = SlotArena::<Box<dyn Node>>::new();
+    let head = arena.reserve_slot_with(Box::new(Link(None)));
+    let tail = arena.insert(Box::new(Link(Some(head))));
+    assert!(!arena.is_valid(head));
+
+    arena.fill(head, Box::new(Link(Some(tail))));
+    assert_eq!(arena.get(head).next(), Some(tail));
+    assert_eq!(arena.get(tail).next(), Some(head));
+    arena.assert_invariants();
+}
+
+#[test]
+fn diff_between_snapshots() {
+    let mut older = SlotArena::new();
+    let refs: Vec<_> = (0..4).map(|value| older.insert(value)).collect();
+
+    let mut newer = older.clone();
+    newer.free(refs[3]);
+    assert_eq!(newer.insert(3), refs[3]);
+    let added = newer.insert(4);
+    newer.free(refs[1]);
+    *newer.get_mut(refs[2]) = 20;
+
+    let diff = older.diff(&newer);
+    assert_eq!(diff.added, [(added, 4)]);
+    assert_eq!(diff.removed, [refs[1]]);
+    assert_eq!(diff.changed, [(refs[2], 2, 20)]);
+
+    let reverse = newer.diff(&older);
+    assert_eq!(reverse.added, [(refs[1], 1)]);
+    assert_eq!(reverse.removed, [added]);
+    assert!(older.diff(&older.clone()).is_empty());
+}
+
+#[test]
+fn preview_inserts_matches_inserts() {
+    for policy in [ReusePolicy::Lifo, ReusePolicy::Fifo] {
+        let mut arena = SlotArena::builder().reuse_policy(policy).build().unwrap();
+        let refs: Vec<_> = (0..5).map(|value| arena.insert(value)).collect();
+        arena.free(refs[3]);
+        arena.free(refs[0]);
+        arena.free(refs[2]);
+
+        let preview = arena.preview_inserts(5);
+        let inserted: Vec<_> = (0..5).map(|value| arena.insert(value)).collect();
+        assert_eq!(preview, inserted, "{policy:?}");
+    }
+
+    let mut arena = SlotArena::builder().bounded(3).build().unwrap();
+    let first = arena.insert(0);
+    arena.insert(1);
+    arena.free(first);
+
+    let preview = arena.preview_inserts(4);
+    let inserted: Vec<_> = std::iter::from_fn(|| arena.try_insert(2)).take(4).collect();
+    assert_eq!(preview.len(), 2);
+    assert_eq!(preview, inserted);
+}
+
+#[test]
+fn partition_rebases_refs() {
+    let mut arena = SlotArena::builder().reuse_policy(ReusePolicy::Fifo).build().unwrap();
+    let refs: Vec<_> = (0..6).map(|value| arena.insert(value)).collect();
+    arena.free(refs[1]);
+
+    let (even, odd) = arena.partition(|_, value| value % 2 == 0);
+    assert_eq!(even.iter().map(|(_, value)| *value).collect::<Vec<_>>(), [0, 2, 4]);
+    assert_eq!(odd.iter().map(|(_, value)| *value).collect::<Vec<_>>(), [3, 5]);
+    assert_eq!(odd.iter().map(|(idx, _)| idx.to_raw()).collect::<Vec<_>>(), [0, 1]);
+    assert_eq!((even.reuse_policy(), even.free_count()), (ReusePolicy::Fifo, 0));
+    even.assert_invariants();
+    odd.assert_invariants();
+}
+
+#[test]
+fn from_parts_errors() {
+    let out_of_bounds = SlotArena::from_parts(vec![0, 1], vec![Ref::from_raw(2)]);
+    assert_eq!(out_of_bounds.err(), Some(ArenaError::FreeOutOfBounds(2)));
+
+    let duplicate = SlotArena::from_parts(vec![0, 1], vec![Ref::from_raw(1), Ref::from_raw(1)]);
+    assert_eq!(duplicate.err(), Some(ArenaError::DuplicateFree(1)));
+
+    let oversized = SlotArena::from_parts(vec![(); u32::MAX as usize + 1], Vec::new());
+    assert_eq!(oversized.err(), Some(ArenaError::CapacityExceeded(u32::MAX)));
+
+    let arena = SlotArena::from_parts(vec![0, 1, 2], vec![Ref::from_raw(1)]).unwrap();
+    assert_eq!(arena.len(), 2);
+    assert!(!arena.is_valid(Ref::from_raw(1)));
+    arena.assert_invariants();
+}
+
+#[test]
+fn free_runs_in_index_order() {
+    let mut arena = SlotArena::new();
+    let refs: Vec<_> = (0..8).map(|value| arena.insert(value)).collect();
+    for idx in [6, 2, 7, 1, 4] {
+        arena.free(refs[idx]);
+    }
+
+    let runs: Vec<_> = arena.free_runs().map(|(idx, len)| (idx.to_raw(), len)).collect();
+    assert_eq!(runs, [(1, 2), (4, 1), (6, 2)]);
+    assert_eq!(SlotArena::<u8>::new().free_runs().count(), 0);
+}
+
+#[test]
+fn try_get2_mut_errors() {
+    let mut arena = SlotArena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let freed = arena.insert(3);
+    arena.free(freed);
+
+    let (x, y) = arena.try_get2_mut(a, b).unwrap();
+    std::mem::swap(x, y);
+    assert_eq!((*arena.get(a), *arena.get(b)), (2, 1));
+
+    assert_eq!(arena.try_get2_mut(a, a).err(), Some(DisjointError::Aliased(0)));
+    assert_eq!(arena.try_get2_mut(a, freed).err(), Some(DisjointError::Invalid(2)));
+    assert_eq!(arena.try_get2_mut(Ref::from_raw(9), b).err(), Some(DisjointError::Invalid(9)));
+}
+
+#[test]
+fn get_mut_and_rejects_aliasing() {
+    let mut arena = SlotArena::new();
+    let refs: Vec<_> = (0..3).map(|value| arena.insert(value)).collect();
+
+    let (target, [left, right]) = arena.get_mut_and(refs[1], [refs[0], refs[2]]).unwrap();
+    *target = left + right;
+    assert_eq!(*arena.get(refs[1]), 2);
+
+    assert!(arena.get_mut_and(refs[1], [refs[0], refs[1]]).is_none());
+    assert!(arena.get_mut_and(refs[0], [refs[2], refs[2]]).is_some());
+    arena.free(refs[2]);
+    assert!(arena.get_mut_and(refs[0], [refs[2]]).is_none());
+}
+
+#[test]
+fn drain_into_iter_keeps_capacity() {
+    let mut arena = SlotArena::with_capacity(16);
+    let refs: Vec<_> = (0..10).map(|value| arena.insert(value)).collect();
+    arena.free(refs[4]);
+    let capacity = arena.raw.capacity();
+
+    let drained: Vec<_> = arena.drain_into_iter().collect();
+    assert_eq!(drained.len(), 9);
+    assert_eq!(drained[4], (refs[5], 5));
+    assert!(arena.is_empty());
+    assert_eq!(arena.raw.capacity(), capacity);
+    arena.assert_invariants();
+
+    assert_eq!(arena.insert(0), Ref::from_raw(0));
+}